@@ -0,0 +1,149 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::repository::{Job, JobRepository, JobRepositoryError};
+
+/// An async handler that runs a single job and reports its output (or a failure message).
+pub type JobHandler =
+    Arc<dyn Fn(Job) -> Pin<Box<dyn Future<Output = Result<Value, String>> + Send>> + Send + Sync>;
+
+/// An in-process worker pool that turns the broker into a self-contained job processor.
+///
+/// Each worker loops on [`JobRepository::dequeue`], runs the registered [`JobHandler`] with
+/// the job's payload, and concludes or fails the job based on the outcome. Handler panics are
+/// caught and converted into failures so a single bad job can't take a worker down with it.
+pub struct JobExecutor {
+    repository: Arc<dyn JobRepository + Send + Sync>,
+    running: Arc<AtomicUsize>,
+}
+
+impl JobExecutor {
+    pub fn new(repository: Arc<dyn JobRepository + Send + Sync>) -> Self {
+        Self {
+            repository,
+            running: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Shared counter of job handlers currently executing across the pool.
+    pub fn running(&self) -> Arc<AtomicUsize> {
+        self.running.clone()
+    }
+
+    /// Launch `workers` tasks that process jobs until the process exits.
+    pub fn spawn(&self, handler: JobHandler, workers: usize) {
+        for _ in 0..workers {
+            let repository = self.repository.clone();
+            let running = self.running.clone();
+            let handler = handler.clone();
+            tokio::spawn(async move { worker_loop(repository, running, handler).await });
+        }
+    }
+}
+
+async fn worker_loop(
+    repository: Arc<dyn JobRepository + Send + Sync>,
+    running: Arc<AtomicUsize>,
+    handler: JobHandler,
+) {
+    loop {
+        match repository.dequeue().await {
+            Ok(job) => {
+                let id = job.id;
+                running.fetch_add(1, Ordering::SeqCst);
+                // Run the handler on its own task so a panic is observed as a `JoinError`
+                // rather than unwinding the worker loop.
+                let outcome = tokio::spawn({
+                    let handler = handler.clone();
+                    async move { handler(job).await }
+                })
+                .await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                match outcome {
+                    Ok(Ok(result)) => {
+                        let _ = repository.conclude_with_result(id, result).await;
+                    }
+                    Ok(Err(err)) => {
+                        tracing::warn!(id, %err, "job handler returned an error");
+                        let _ = repository.fail(id).await;
+                    }
+                    Err(join_err) => {
+                        tracing::error!(id, %join_err, "job handler panicked");
+                        let _ = repository.fail(id).await;
+                    }
+                }
+            }
+            Err(JobRepositoryError::Empty) => {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Err(err) => {
+                tracing::error!(%err, "worker failed to dequeue a job");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::repository::job_repository::InMemoryJobRepository;
+    #[allow(unused_imports)]
+    use crate::repository::{JobStatus, JobType};
+
+    #[tokio::test]
+    async fn test_worker_concludes_with_handler_result() {
+        // given a worker that echoes each job's payload back as its result
+        let repository = Arc::new(InMemoryJobRepository::new());
+        let job = repository
+            .enqueue_with_payload(JobType::TimeCritical, Some(serde_json::json!({ "n": 7 })))
+            .await
+            .unwrap();
+        let executor = JobExecutor::new(repository.clone());
+        let handler: JobHandler = Arc::new(|job| {
+            Box::pin(async move { Ok(job.payload.unwrap_or(Value::Null)) })
+        });
+        // when
+        executor.spawn(handler, 1);
+        // then the job eventually concludes carrying the handler's result
+        let mut concluded = None;
+        for _ in 0..50 {
+            let found = repository.find(job.id).await.unwrap();
+            if found.status == JobStatus::Concluded {
+                concluded = Some(found);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let concluded = concluded.expect("job should have concluded");
+        assert_eq!(concluded.result, Some(serde_json::json!({ "n": 7 })));
+    }
+
+    #[tokio::test]
+    async fn test_worker_fails_job_on_handler_panic() {
+        // given a handler that always panics
+        let repository = Arc::new(InMemoryJobRepository::with_max_attempts(1));
+        let job = repository.enqueue(JobType::TimeCritical).await.unwrap();
+        let executor = JobExecutor::new(repository.clone());
+        let handler: JobHandler =
+            Arc::new(|_job| Box::pin(async move { panic!("boom") }));
+        // when
+        executor.spawn(handler, 1);
+        // then the panic is caught and the job is failed into the dead-letter state
+        let mut dead = false;
+        for _ in 0..50 {
+            if repository.find(job.id).await.unwrap().status == JobStatus::Dead {
+                dead = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(dead, "panicking handler should fail the job");
+    }
+}
@@ -1,12 +1,18 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
+
 use tokio::time::Instant;
 
-use crate::repository::job_repository::InMemoryJobRepository;
+use crate::repository::JobRepository;
 
+pub mod executor;
 pub mod repository;
 pub mod web;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub job_repository: InMemoryJobRepository,
+    pub job_repository: Arc<dyn JobRepository + Send + Sync>,
     pub uptime: Instant,
+    /// Count of job handlers currently executing in the embedded worker pool.
+    pub running: Arc<AtomicUsize>,
 }
@@ -1,14 +1,27 @@
 use std::env;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use tokio::time::Instant;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
+use cfqueue::executor::{JobExecutor, JobHandler};
 use cfqueue::repository::job_repository::InMemoryJobRepository;
+use cfqueue::repository::sled_repository::SledJobRepository;
+use cfqueue::repository::JobRepository;
 use cfqueue::web;
 use cfqueue::web::AppState;
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Storage {
+    /// Volatile in-memory queue; everything is lost on restart.
+    Mem,
+    /// Crash-safe queue backed by an embedded `sled` store on disk.
+    Sled,
+}
+
 #[derive(Parser, Debug)]
 #[command(
     version,
@@ -27,6 +40,18 @@ struct Args {
     /// Root logging level.
     #[arg(short, long, default_value_t = String::from("debug"))]
     log_level: String,
+
+    /// Storage backend used to hold the queue.
+    #[arg(short, long, value_enum, default_value_t = Storage::Mem)]
+    storage: Storage,
+
+    /// Directory for the `sled` backend's on-disk data (ignored for `mem`).
+    #[arg(long, default_value_t = String::from("./cfqueue-data"))]
+    data_dir: String,
+
+    /// Number of embedded worker tasks that process jobs in-process (0 disables the pool).
+    #[arg(short, long, default_value_t = 0)]
+    workers: usize,
 }
 
 #[tokio::main]
@@ -45,11 +70,47 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let job_repository: Arc<dyn JobRepository + Send + Sync> = match args.storage {
+        Storage::Mem => Arc::new(InMemoryJobRepository::new()),
+        Storage::Sled => Arc::new(
+            SledJobRepository::open(&args.data_dir).expect("failed to open sled data directory"),
+        ),
+    };
+
+    // Optionally run an embedded worker pool so the broker can also process jobs. The
+    // built-in handler simply echoes a job's payload back as its result; real deployments
+    // would register their own handler against the `JobExecutor` API.
+    let running = if args.workers > 0 {
+        let executor = JobExecutor::new(job_repository.clone());
+        let running = executor.running();
+        let handler: JobHandler = Arc::new(|job| {
+            Box::pin(async move { Ok(job.payload.unwrap_or(serde_json::Value::Null)) })
+        });
+        executor.spawn(handler, args.workers);
+        running
+    } else {
+        Arc::new(AtomicUsize::new(0))
+    };
+
     let state = AppState {
-        job_repository: InMemoryJobRepository::new(),
+        job_repository: job_repository.clone(),
         uptime: Instant::now(),
+        running,
     };
 
+    // Periodically reclaim jobs whose worker lease has expired so a crashed worker
+    // doesn't strand its job in `InProgress` forever.
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(5));
+        loop {
+            ticker.tick().await;
+            let reclaimed = job_repository.reclaim_expired().await;
+            if !reclaimed.is_empty() {
+                tracing::info!(?reclaimed, "reclaimed jobs with expired leases");
+            }
+        }
+    });
+
     let app = web::build_server(state);
 
     let listener = tokio::net::TcpListener::bind(format!("{}:{}", server_addr, server_port))
@@ -1,15 +1,65 @@
 use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::Instant;
+use uuid::Uuid;
 
 use crate::repository::{Job, JobRepository, JobRepositoryError, JobStatus, JobType};
 
-#[derive(Default, Clone)]
+/// A worker's claim on an in-progress job: who holds it and when the claim lapses.
+#[derive(Clone, Copy)]
+struct Lease {
+    runner_id: Uuid,
+    deadline: Instant,
+}
+
+/// Default visibility timeout applied to a dequeued job before the reaper reclaims it.
+const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(30);
+
+/// Default number of attempts a job gets before it is moved to the dead-letter state.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Base delay used to compute the exponential backoff applied before a failed job is retried.
+const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(100);
+
+#[derive(Clone)]
 pub struct InMemoryJobRepository {
     jobs: Arc<Mutex<HashMap<usize, Job>>>,
-    queue: Arc<Mutex<VecDeque<Job>>>,
+    /// FIFO lane for `JobType::TimeCritical` jobs, always drained before the standard lane.
+    time_critical_queue: Arc<Mutex<VecDeque<Job>>>,
+    /// FIFO lane for `JobType::NotTimeCritical` jobs.
+    not_time_critical_queue: Arc<Mutex<VecDeque<Job>>>,
+    /// Lease (owner + deadline) for each currently in-progress job.
+    leases: Arc<Mutex<HashMap<usize, Lease>>>,
+    lease_duration: Duration,
+    /// Attempts a job gets before it is moved to the dead-letter state.
+    max_attempts: u32,
+    /// Monotonically increasing id source, so ids never collide even if jobs are removed.
+    next_id: Arc<AtomicUsize>,
+    /// Base delay for the exponential retry backoff.
+    base_backoff: Duration,
+    /// Signalled whenever a job is pushed into a lane, so blocked `dequeue_wait` callers wake.
+    available: Arc<Notify>,
+}
+
+impl Default for InMemoryJobRepository {
+    fn default() -> Self {
+        Self {
+            jobs: Arc::default(),
+            time_critical_queue: Arc::default(),
+            not_time_critical_queue: Arc::default(),
+            leases: Arc::default(),
+            lease_duration: DEFAULT_LEASE_DURATION,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            next_id: Arc::new(AtomicUsize::new(0)),
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            available: Arc::default(),
+        }
+    }
 }
 
 impl InMemoryJobRepository {
@@ -19,50 +69,176 @@ impl InMemoryJobRepository {
         }
     }
 
-    /// Collect the current queue and job stats.
-    ///
-    /// The output is a tuple with the following format (`<queue len>`, `<queued jobs>`, `<in progress jobs>`, `<concluded jobs>`)
+    /// Build a repository with a custom visibility timeout for dequeued jobs.
+    pub fn with_lease_duration(lease_duration: Duration) -> Self {
+        Self {
+            lease_duration,
+            ..Default::default()
+        }
+    }
+
+    /// Build a repository with a custom maximum number of attempts per job.
+    pub fn with_max_attempts(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// Re-insert a job into its priority lane, preserving the critical/standard split.
+    async fn requeue(&self, job: &Job) {
+        match job.job_type {
+            JobType::TimeCritical => self.time_critical_queue.lock().await.push_back(job.clone()),
+            JobType::NotTimeCritical => self
+                .not_time_critical_queue
+                .lock()
+                .await
+                .push_back(job.clone()),
+        }
+        // Wake a caller parked in `dequeue_wait`, if any.
+        self.available.notify_one();
+    }
+
+    /// Remove and return the first job in `lane` that is ready to run (its `run_at`, if any,
+    /// has passed), leaving any future-dated jobs in place and preserving FIFO among the rest.
+    async fn pop_ready(&self, lane: &Mutex<VecDeque<Job>>) -> Option<Job> {
+        let now = SystemTime::now();
+        let mut queue = lane.lock().await;
+        let idx = queue
+            .iter()
+            .position(|job| job.run_at.map_or(true, |run_at| run_at <= now))?;
+        queue.remove(idx)
+    }
+
+    /// Exponential backoff applied before a job that has failed `attempts` times is retried.
+    fn backoff(&self, attempts: u32) -> Duration {
+        self.base_backoff * 2u32.saturating_pow(attempts)
+    }
+
+    /// Make a job dequeuable again after its retry backoff has elapsed.
     ///
-    /// The method is not included in public API as it is used for only debugging purpose.
-    pub(crate) async fn stats(&self) -> (usize, usize, usize, usize) {
-        let jobs = self.jobs.lock().await;
-        let job_stats = jobs.values().fold(
-            (0usize, 0usize, 0usize, 0usize),
-            |(queued, in_progress, concluded, cancelled), job| match job.status {
-                JobStatus::Queued => (queued + 1, in_progress, concluded, cancelled),
-                JobStatus::InProgress => (queued, in_progress + 1, concluded, cancelled),
-                JobStatus::Concluded => (queued, in_progress, concluded + 1, cancelled),
-                JobStatus::Cancelled => (queued, in_progress, concluded, cancelled + 1),
-            },
-        );
-        (job_stats.0, job_stats.1, job_stats.2, job_stats.3)
+    /// The job is already marked `Queued`; we just defer re-inserting it into its lane so it
+    /// isn't handed straight back out before the backoff window passes.
+    async fn requeue_after_backoff(&self, job: Job) {
+        let delay = self.backoff(job.attempts);
+        let repository = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            repository.requeue(&job).await;
+        });
     }
 }
 
 #[async_trait]
 impl JobRepository for InMemoryJobRepository {
-    async fn enqueue(&self, job_type: JobType) -> Result<Job, JobRepositoryError> {
+    async fn stats(&self) -> (usize, usize, usize, usize, usize) {
+        let jobs = self.jobs.lock().await;
+        jobs.values().fold(
+            (0usize, 0usize, 0usize, 0usize, 0usize),
+            |(queued, in_progress, concluded, cancelled, dead), job| match job.status {
+                JobStatus::Queued => (queued + 1, in_progress, concluded, cancelled, dead),
+                JobStatus::InProgress => (queued, in_progress + 1, concluded, cancelled, dead),
+                JobStatus::Concluded => (queued, in_progress, concluded + 1, cancelled, dead),
+                JobStatus::Cancelled => (queued, in_progress, concluded, cancelled + 1, dead),
+                JobStatus::Dead => (queued, in_progress, concluded, cancelled, dead + 1),
+            },
+        )
+    }
+
+    async fn enqueue_with_payload(
+        &self,
+        job_type: JobType,
+        payload: Option<serde_json::Value>,
+    ) -> Result<Job, JobRepositoryError> {
         let mut jobs = self.jobs.lock().await;
-        let id = jobs.len() + 1;
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
         let job = Job {
             id,
             job_type,
             status: JobStatus::Queued,
+            attempts: 0,
+            max_attempts: self.max_attempts,
+            payload,
+            result: None,
+            run_at: None,
         };
-        let mut queue = self.queue.lock().await;
-        queue.push_back(job.clone());
+        self.requeue(&job).await;
         jobs.insert(id, job.clone());
         Ok(job)
     }
 
+    async fn enqueue_at(
+        &self,
+        job_type: JobType,
+        run_at: SystemTime,
+    ) -> Result<Job, JobRepositoryError> {
+        let mut jobs = self.jobs.lock().await;
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let job = Job {
+            id,
+            job_type,
+            status: JobStatus::Queued,
+            attempts: 0,
+            max_attempts: self.max_attempts,
+            payload: None,
+            result: None,
+            run_at: Some(run_at),
+        };
+        self.requeue(&job).await;
+        jobs.insert(id, job.clone());
+        Ok(job)
+    }
+
+    async fn enqueue_many(
+        &self,
+        requests: Vec<(JobType, Option<serde_json::Value>)>,
+    ) -> Result<Vec<Job>, JobRepositoryError> {
+        // Allocate all ids and insert the whole batch under a single `jobs` lock so
+        // concurrent callers can't interleave and observe a half-applied batch.
+        let mut jobs = self.jobs.lock().await;
+        let mut enqueued = Vec::with_capacity(requests.len());
+        for (job_type, payload) in requests {
+            let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+            let job = Job {
+                id,
+                job_type,
+                status: JobStatus::Queued,
+                attempts: 0,
+                max_attempts: self.max_attempts,
+                payload,
+                result: None,
+                run_at: None,
+            };
+            self.requeue(&job).await;
+            jobs.insert(id, job.clone());
+            enqueued.push(job);
+        }
+        Ok(enqueued)
+    }
+
     async fn dequeue(&self) -> Result<Job, JobRepositoryError> {
-        match self.queue.lock().await.pop_back() {
+        // Serve the whole time-critical lane before touching the standard lane, skipping any
+        // scheduled jobs whose `run_at` is still in the future.
+        let queued_job = match self.pop_ready(&self.time_critical_queue).await {
+            Some(job) => Some(job),
+            None => self.pop_ready(&self.not_time_critical_queue).await,
+        };
+        match queued_job {
             Some(queued_job) => {
                 let mut jobs = self.jobs.lock().await;
                 match jobs.get_mut(&queued_job.id) {
                     Some(job) if job.status == JobStatus::Queued => {
                         job.status = JobStatus::InProgress;
-                        Ok(job.clone())
+                        let job = job.clone();
+                        // Stamp the visibility timeout so a crashed worker's job can be reclaimed.
+                        self.leases.lock().await.insert(
+                            job.id,
+                            Lease {
+                                runner_id: Uuid::nil(),
+                                deadline: Instant::now() + self.lease_duration,
+                            },
+                        );
+                        Ok(job)
                     }
                     _ => Err(JobRepositoryError::Unknown),
                 }
@@ -71,12 +247,55 @@ impl JobRepository for InMemoryJobRepository {
         }
     }
 
+    async fn dequeue_wait(&self, timeout: Duration) -> Result<Job, JobRepositoryError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.dequeue().await {
+                Err(JobRepositoryError::Empty) => {}
+                other => return other,
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(JobRepositoryError::Empty);
+            }
+            // Block until the next enqueue signal or the remaining window elapses, then loop
+            // and retry the dequeue (a future-dated job may also have become ready meanwhile).
+            if tokio::time::timeout(remaining, self.available.notified())
+                .await
+                .is_err()
+            {
+                return self.dequeue().await;
+            }
+        }
+    }
+
     async fn conclude(&self, id: usize) -> Result<Job, JobRepositoryError> {
         let mut jobs = self.jobs.lock().await;
         match jobs.get_mut(&id) {
             Some(job) if job.status == JobStatus::InProgress => {
                 job.status = JobStatus::Concluded;
-                Ok(job.clone())
+                let job = job.clone();
+                self.leases.lock().await.remove(&id);
+                Ok(job)
+            }
+            Some(_) => Err(JobRepositoryError::InvalidStatus(id)),
+            None => Err(JobRepositoryError::NotFound(id)),
+        }
+    }
+
+    async fn conclude_with_result(
+        &self,
+        id: usize,
+        result: serde_json::Value,
+    ) -> Result<Job, JobRepositoryError> {
+        let mut jobs = self.jobs.lock().await;
+        match jobs.get_mut(&id) {
+            Some(job) if job.status == JobStatus::InProgress => {
+                job.status = JobStatus::Concluded;
+                job.result = Some(result);
+                let job = job.clone();
+                self.leases.lock().await.remove(&id);
+                Ok(job)
             }
             Some(_) => Err(JobRepositoryError::InvalidStatus(id)),
             None => Err(JobRepositoryError::NotFound(id)),
@@ -93,16 +312,18 @@ impl JobRepository for InMemoryJobRepository {
     }
 
     async fn cancel(&self, id: usize) -> Result<Job, JobRepositoryError> {
-        let mut queue = self.queue.lock().await;
-        let ids: Vec<usize> = queue
-            .iter()
-            .filter(|job| job.status == JobStatus::Cancelled)
-            .enumerate()
-            .map(|(idx, _)| idx)
-            .collect();
-        ids.iter().for_each(|idx| {
-            queue.remove(*idx);
-        });
+        for lane in [&self.time_critical_queue, &self.not_time_critical_queue] {
+            let mut queue = lane.lock().await;
+            let ids: Vec<usize> = queue
+                .iter()
+                .filter(|job| job.status == JobStatus::Cancelled)
+                .enumerate()
+                .map(|(idx, _)| idx)
+                .collect();
+            ids.iter().for_each(|idx| {
+                queue.remove(*idx);
+            });
+        }
         let mut jobs = self.jobs.lock().await;
         return match jobs.get_mut(&id) {
             None => Err(JobRepositoryError::NotFound(id)),
@@ -115,6 +336,141 @@ impl JobRepository for InMemoryJobRepository {
             },
         };
     }
+
+    async fn fail(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        let job = {
+            let mut jobs = self.jobs.lock().await;
+            match jobs.get_mut(&id) {
+                Some(job) if job.status == JobStatus::InProgress => {
+                    job.attempts += 1;
+                    job.status = if job.attempts < job.max_attempts {
+                        JobStatus::Queued
+                    } else {
+                        JobStatus::Dead
+                    };
+                    job.clone()
+                }
+                Some(_) => return Err(JobRepositoryError::InvalidStatus(id)),
+                None => return Err(JobRepositoryError::NotFound(id)),
+            }
+        };
+        self.leases.lock().await.remove(&id);
+        if job.status == JobStatus::Queued {
+            // Defer re-insertion by the exponential backoff for this attempt count.
+            self.requeue_after_backoff(job.clone()).await;
+        }
+        Ok(job)
+    }
+
+    async fn dead(&self) -> Vec<Job> {
+        let mut dead: Vec<Job> = self
+            .jobs
+            .lock()
+            .await
+            .values()
+            .filter(|job| job.status == JobStatus::Dead)
+            .cloned()
+            .collect();
+        dead.sort_by_key(|job| job.id);
+        dead
+    }
+
+    async fn heartbeat(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        let jobs = self.jobs.lock().await;
+        match jobs.get(&id) {
+            Some(job) if job.status == JobStatus::InProgress => {
+                let runner_id = self
+                    .leases
+                    .lock()
+                    .await
+                    .get(&id)
+                    .map(|lease| lease.runner_id)
+                    .unwrap_or_else(Uuid::nil);
+                self.leases.lock().await.insert(
+                    id,
+                    Lease {
+                        runner_id,
+                        deadline: Instant::now() + self.lease_duration,
+                    },
+                );
+                Ok(job.clone())
+            }
+            Some(_) => Err(JobRepositoryError::InvalidStatus(id)),
+            None => Err(JobRepositoryError::NotFound(id)),
+        }
+    }
+
+    async fn dequeue_as(
+        &self,
+        runner_id: Uuid,
+        lease: Duration,
+    ) -> Result<Job, JobRepositoryError> {
+        let job = self.dequeue().await?;
+        self.leases.lock().await.insert(
+            job.id,
+            Lease {
+                runner_id,
+                deadline: Instant::now() + lease,
+            },
+        );
+        Ok(job)
+    }
+
+    async fn heartbeat_as(
+        &self,
+        id: usize,
+        runner_id: Uuid,
+    ) -> Result<Job, JobRepositoryError> {
+        {
+            let mut leases = self.leases.lock().await;
+            match leases.get_mut(&id) {
+                Some(lease) if lease.runner_id == runner_id => {
+                    lease.deadline = Instant::now() + self.lease_duration;
+                }
+                Some(_) => return Err(JobRepositoryError::InvalidStatus(id)),
+                None => return Err(JobRepositoryError::NotFound(id)),
+            }
+        }
+        self.find(id).await
+    }
+
+    async fn reclaim_expired(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let expired: Vec<usize> = {
+            let leases = self.leases.lock().await;
+            leases
+                .iter()
+                .filter(|(_, lease)| lease.deadline <= now)
+                .map(|(id, _)| *id)
+                .collect()
+        };
+        let mut reclaimed = Vec::new();
+        for id in expired {
+            let job = {
+                let mut jobs = self.jobs.lock().await;
+                match jobs.get_mut(&id) {
+                    Some(job) if job.status == JobStatus::InProgress => {
+                        job.attempts += 1;
+                        job.status = if job.attempts < job.max_attempts {
+                            JobStatus::Queued
+                        } else {
+                            JobStatus::Dead
+                        };
+                        Some(job.clone())
+                    }
+                    _ => None,
+                }
+            };
+            self.leases.lock().await.remove(&id);
+            if let Some(job) = job {
+                if job.status == JobStatus::Queued {
+                    self.requeue_after_backoff(job.clone()).await;
+                }
+                reclaimed.push(id);
+            }
+        }
+        reclaimed
+    }
 }
 
 mod tests {
@@ -151,6 +507,30 @@ mod tests {
         assert_eq!(job.status, JobStatus::Queued);
     }
 
+    #[tokio::test]
+    async fn test_enqueue_many_and_find_many() {
+        // given
+        let job_repository = InMemoryJobRepository::new();
+        // when
+        let jobs = job_repository
+            .enqueue_many(vec![
+                (JobType::TimeCritical, None),
+                (JobType::NotTimeCritical, Some(serde_json::json!({ "n": 1 }))),
+            ])
+            .await
+            .unwrap();
+        // then ids are distinct and contiguous
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].id, 1);
+        assert_eq!(jobs[1].id, 2);
+        // and they can be looked up in bulk
+        let found = job_repository.find_many(&[1, 2, 99]).await;
+        assert_eq!(found.len(), 2);
+        assert!(found.contains_key(&1));
+        assert!(found.contains_key(&2));
+        assert!(!found.contains_key(&99));
+    }
+
     #[tokio::test]
     async fn test_dequeue_when_empty() {
         // given
@@ -190,6 +570,192 @@ mod tests {
         assert_eq!(deq_job.status, JobStatus::InProgress);
     }
 
+    #[tokio::test]
+    async fn test_dequeue_serves_time_critical_first() {
+        // given
+        let job_repository = InMemoryJobRepository::new();
+        let not_critical = job_repository
+            .enqueue(JobType::NotTimeCritical)
+            .await
+            .unwrap();
+        let critical = job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        // when
+        let first = job_repository.dequeue().await.unwrap();
+        let second = job_repository.dequeue().await.unwrap();
+        // then
+        assert_eq!(first.id, critical.id);
+        assert_eq!(first.job_type, JobType::TimeCritical);
+        assert_eq!(second.id, not_critical.id);
+        assert_eq!(second.job_type, JobType::NotTimeCritical);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reclaim_expired_returns_job_to_queue() {
+        // given
+        let job_repository = InMemoryJobRepository::with_lease_duration(Duration::from_secs(5));
+        job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        let job = job_repository.dequeue().await.unwrap();
+        assert_eq!(job.status, JobStatus::InProgress);
+        // when the lease elapses and the reaper runs
+        tokio::time::advance(Duration::from_secs(6)).await;
+        let reclaimed = job_repository.reclaim_expired().await;
+        // then
+        assert_eq!(reclaimed, vec![job.id]);
+        let job = job_repository.find(job.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.attempts, 1);
+        // and it can be handed out again once the retry backoff elapses
+        tokio::time::advance(Duration::from_secs(1)).await;
+        tokio::task::yield_now().await;
+        let again = job_repository.dequeue().await.unwrap();
+        assert_eq!(again.id, job.id);
+        assert_eq!(again.status, JobStatus::InProgress);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeat_as_checks_ownership() {
+        // given a job claimed by a specific runner
+        let job_repository = InMemoryJobRepository::with_lease_duration(Duration::from_secs(5));
+        job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        let runner = Uuid::from_u128(1);
+        let other = Uuid::from_u128(2);
+        let job = job_repository
+            .dequeue_as(runner, Duration::from_secs(5))
+            .await
+            .unwrap();
+        // a different runner cannot extend someone else's lease
+        assert_eq!(
+            job_repository.heartbeat_as(job.id, other).await.expect_err("fail"),
+            JobRepositoryError::InvalidStatus(job.id)
+        );
+        // the owner can, which keeps the reaper from reclaiming it
+        tokio::time::advance(Duration::from_secs(4)).await;
+        job_repository.heartbeat_as(job.id, runner).await.unwrap();
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert!(job_repository.reclaim_expired().await.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeat_keeps_job_leased() {
+        // given
+        let job_repository = InMemoryJobRepository::with_lease_duration(Duration::from_secs(5));
+        job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        let job = job_repository.dequeue().await.unwrap();
+        // when a live worker keeps pushing its deadline forward
+        tokio::time::advance(Duration::from_secs(4)).await;
+        job_repository.heartbeat(job.id).await.unwrap();
+        tokio::time::advance(Duration::from_secs(4)).await;
+        let reclaimed = job_repository.reclaim_expired().await;
+        // then nothing is reclaimed while the lease is fresh
+        assert!(reclaimed.is_empty());
+        assert_eq!(
+            job_repository.find(job.id).await.unwrap().status,
+            JobStatus::InProgress
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dequeue_priority_preserves_fifo_within_class() {
+        // given an interleaved mix of critical and non-critical jobs
+        let job_repository = InMemoryJobRepository::new();
+        let n1 = job_repository
+            .enqueue(JobType::NotTimeCritical)
+            .await
+            .unwrap();
+        let c1 = job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        let n2 = job_repository
+            .enqueue(JobType::NotTimeCritical)
+            .await
+            .unwrap();
+        let c2 = job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        // when draining the whole queue
+        let order: Vec<usize> = vec![
+            job_repository.dequeue().await.unwrap().id,
+            job_repository.dequeue().await.unwrap().id,
+            job_repository.dequeue().await.unwrap().id,
+            job_repository.dequeue().await.unwrap().id,
+        ];
+        // then both critical jobs come first (FIFO), then both non-critical jobs (FIFO)
+        assert_eq!(order, vec![c1.id, c2.id, n1.id, n2.id]);
+    }
+
+    #[tokio::test]
+    async fn test_dequeue_wait_wakes_on_enqueue() {
+        // given a caller blocked on an empty queue
+        let job_repository = InMemoryJobRepository::new();
+        let waiter = job_repository.clone();
+        let handle = tokio::spawn(async move { waiter.dequeue_wait(Duration::from_secs(10)).await });
+        tokio::task::yield_now().await;
+        // when a job is enqueued from elsewhere
+        let enqueued = job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        // then the blocked caller is handed that job
+        let job = handle.await.unwrap().unwrap();
+        assert_eq!(job.id, enqueued.id);
+        assert_eq!(job.status, JobStatus::InProgress);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dequeue_wait_times_out() {
+        // given an empty queue and a bounded wait
+        let job_repository = InMemoryJobRepository::new();
+        let waiter = job_repository.clone();
+        let handle =
+            tokio::spawn(async move { waiter.dequeue_wait(Duration::from_millis(50)).await });
+        tokio::task::yield_now().await;
+        // when the window elapses without any enqueue
+        tokio::time::advance(Duration::from_millis(60)).await;
+        // then the caller gives up with the empty-queue error
+        assert_eq!(handle.await.unwrap().expect_err("fail"), JobRepositoryError::Empty);
+    }
+
+    #[tokio::test]
+    async fn test_dequeue_skips_future_dated_jobs() {
+        // given a job scheduled for the future and a plain, ready one
+        let job_repository = InMemoryJobRepository::new();
+        let scheduled = job_repository
+            .enqueue_at(
+                JobType::TimeCritical,
+                SystemTime::now() + Duration::from_secs(3600),
+            )
+            .await
+            .unwrap();
+        let ready = job_repository
+            .enqueue(JobType::NotTimeCritical)
+            .await
+            .unwrap();
+        // when draining the queue
+        let first = job_repository.dequeue().await.unwrap();
+        // then the ready job is served even though the scheduled one sits ahead of it
+        assert_eq!(first.id, ready.id);
+        // and the future-dated job is still withheld
+        assert_eq!(
+            job_repository.dequeue().await.expect_err("fail"),
+            JobRepositoryError::Empty
+        );
+        assert_eq!(
+            job_repository.find(scheduled.id).await.unwrap().status,
+            JobStatus::Queued
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_at_in_the_past_is_ready() {
+        // given a job whose run-at time has already passed
+        let job_repository = InMemoryJobRepository::new();
+        let job = job_repository
+            .enqueue_at(
+                JobType::TimeCritical,
+                SystemTime::now() - Duration::from_secs(1),
+            )
+            .await
+            .unwrap();
+        // when
+        let dequeued = job_repository.dequeue().await.unwrap();
+        // then it is handed out immediately
+        assert_eq!(dequeued.id, job.id);
+        assert_eq!(dequeued.status, JobStatus::InProgress);
+    }
+
     #[tokio::test]
     async fn test_conclude_when_not_found() {
         // given
@@ -229,6 +795,53 @@ mod tests {
         assert_eq!(job.job_type, JobType::NotTimeCritical);
     }
 
+    #[test]
+    fn test_job_payload_and_result_round_trip() {
+        // given a concluded job carrying both a payload and a result
+        let job = Job {
+            id: 7,
+            job_type: JobType::TimeCritical,
+            status: JobStatus::Concluded,
+            attempts: 1,
+            max_attempts: 3,
+            payload: Some(serde_json::json!({ "url": "https://example.com" })),
+            result: Some(serde_json::json!({ "status": 200 })),
+            run_at: None,
+        };
+        // when serialized for the wire and parsed back
+        let encoded = serde_json::to_value(&job).unwrap();
+        // then the public field names are preserved and the values survive the trip
+        assert_eq!(encoded["Payload"], serde_json::json!({ "url": "https://example.com" }));
+        assert_eq!(encoded["Result"], serde_json::json!({ "status": 200 }));
+        let decoded: Job = serde_json::from_value(encoded).unwrap();
+        assert_eq!(decoded.payload, job.payload);
+        assert_eq!(decoded.result, job.result);
+    }
+
+    #[tokio::test]
+    async fn test_conclude_with_result_is_retained() {
+        // given
+        let job_repository = InMemoryJobRepository::new();
+        let payload = serde_json::json!({ "url": "https://example.com" });
+        job_repository
+            .enqueue_with_payload(JobType::TimeCritical, Some(payload.clone()))
+            .await
+            .unwrap();
+        let job = job_repository.dequeue().await.unwrap();
+        assert_eq!(job.payload, Some(payload));
+        // when
+        let result = serde_json::json!({ "status": 200 });
+        let concluded = job_repository
+            .conclude_with_result(job.id, result.clone())
+            .await
+            .unwrap();
+        // then
+        assert_eq!(concluded.status, JobStatus::Concluded);
+        assert_eq!(concluded.result, Some(result.clone()));
+        let found = job_repository.find(job.id).await.unwrap();
+        assert_eq!(found.result, Some(result));
+    }
+
     #[tokio::test]
     async fn test_find() {
         let job_repository = InMemoryJobRepository::new();
@@ -291,12 +904,41 @@ mod tests {
 
         job_repository.conclude(job.id).await.unwrap();
         // when
-        let (queued, in_progress, concluded, cancelled) = job_repository.stats().await;
+        let (queued, in_progress, concluded, cancelled, dead) =
+            job_repository.stats().await;
         // then
         assert_eq!(queued, 3);
         assert_eq!(in_progress, 2);
         assert_eq!(concluded, 1);
         assert_eq!(cancelled, 0);
+        assert_eq!(dead, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fail_retries_then_lands_in_dead() {
+        // given a job that is allowed two attempts
+        let job_repository = InMemoryJobRepository::with_max_attempts(2);
+        job_repository.enqueue(JobType::TimeCritical).await.unwrap();
+        // first failure returns it to the queue
+        let job = job_repository.dequeue().await.unwrap();
+        let job = job_repository.fail(job.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.attempts, 1);
+        // second failure exhausts the attempts and buries it, after the retry backoff
+        tokio::time::advance(Duration::from_secs(1)).await;
+        tokio::task::yield_now().await;
+        let job = job_repository.dequeue().await.unwrap();
+        let job = job_repository.fail(job.id).await.unwrap();
+        assert_eq!(job.status, JobStatus::Dead);
+        assert_eq!(job.attempts, 2);
+        // a dead job is no longer handed out but is retained for inspection
+        assert_eq!(
+            job_repository.dequeue().await.expect_err("fail"),
+            JobRepositoryError::Empty
+        );
+        let dead = job_repository.dead().await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].id, job.id);
     }
 
     #[tokio::test]
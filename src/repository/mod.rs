@@ -1,8 +1,13 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 pub mod job_repository;
+pub mod sled_repository;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Job {
@@ -12,6 +17,21 @@ pub struct Job {
     pub job_type: JobType,
     #[serde(rename = "Status")]
     pub status: JobStatus,
+    /// Number of failed attempts so far: bumped by each `fail` and each lease-expiry reclaim.
+    #[serde(rename = "Attempts", default)]
+    pub attempts: u32,
+    /// Maximum attempts before the job is moved to the terminal [`JobStatus::Dead`] state.
+    #[serde(rename = "MaxAttempts", default)]
+    pub max_attempts: u32,
+    /// Work description submitted at enqueue time, if any.
+    #[serde(rename = "Payload", default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+    /// Output reported by the worker when the job concludes, if any.
+    #[serde(rename = "Result", default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// Earliest time the job may be dequeued; `None` means it is ready immediately.
+    #[serde(rename = "RunAt", default, skip_serializing_if = "Option::is_none")]
+    pub run_at: Option<std::time::SystemTime>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -32,6 +52,8 @@ pub enum JobStatus {
     Concluded,
     #[serde(rename = "CANCELLED")]
     Cancelled,
+    #[serde(rename = "DEAD")]
+    Dead,
 }
 
 #[derive(Debug, Error, Serialize, Deserialize, PartialEq, Clone)]
@@ -48,14 +70,118 @@ pub enum JobRepositoryError {
 
 #[async_trait]
 pub trait JobRepository {
-    /// Add a job to the queue
-    async fn enqueue(&self, job_type: JobType) -> Result<Job, JobRepositoryError>;
+    /// Add a job to the queue, optionally carrying a work payload.
+    async fn enqueue_with_payload(
+        &self,
+        job_type: JobType,
+        payload: Option<serde_json::Value>,
+    ) -> Result<Job, JobRepositoryError>;
+    /// Add a job to the queue with no payload.
+    async fn enqueue(&self, job_type: JobType) -> Result<Job, JobRepositoryError> {
+        self.enqueue_with_payload(job_type, None).await
+    }
+    /// Add a job that does not become dequeuable until `run_at`.
+    ///
+    /// Backends that don't support scheduling fall back to enqueuing immediately.
+    async fn enqueue_at(
+        &self,
+        job_type: JobType,
+        _run_at: std::time::SystemTime,
+    ) -> Result<Job, JobRepositoryError> {
+        self.enqueue(job_type).await
+    }
+    /// Add several jobs to the queue in one go.
+    ///
+    /// Backends can override this to allocate ids and insert the whole batch under a single
+    /// lock acquisition; the default simply enqueues each job in turn.
+    async fn enqueue_many(
+        &self,
+        jobs: Vec<(JobType, Option<serde_json::Value>)>,
+    ) -> Result<Vec<Job>, JobRepositoryError> {
+        let mut enqueued = Vec::with_capacity(jobs.len());
+        for (job_type, payload) in jobs {
+            enqueued.push(self.enqueue_with_payload(job_type, payload).await?);
+        }
+        Ok(enqueued)
+    }
     /// Returns a job from the queue.
     async fn dequeue(&self) -> Result<Job, JobRepositoryError>;
+    /// Dequeue a job on behalf of a specific worker, recording `runner_id` as the lease owner
+    /// and applying `lease` as the visibility timeout. Only that owner may later extend the
+    /// lease via [`JobRepository::heartbeat_as`].
+    ///
+    /// Backends without worker-scoped leases ignore the owner and fall back to [`JobRepository::dequeue`].
+    async fn dequeue_as(
+        &self,
+        _runner_id: uuid::Uuid,
+        _lease: std::time::Duration,
+    ) -> Result<Job, JobRepositoryError> {
+        self.dequeue().await
+    }
+    /// Like [`JobRepository::dequeue`], but park the caller until a job becomes available or
+    /// `timeout` elapses, instead of returning [`JobRepositoryError::Empty`] straight away.
+    ///
+    /// Backends without a blocking primitive fall back to a single immediate attempt.
+    async fn dequeue_wait(
+        &self,
+        _timeout: std::time::Duration,
+    ) -> Result<Job, JobRepositoryError> {
+        self.dequeue().await
+    }
     /// Provided an input of a job ID, finish execution on the job and consider it done
     async fn conclude(&self, id: usize) -> Result<Job, JobRepositoryError>;
+    /// Finish execution on a job, storing the result the worker produced alongside it.
+    async fn conclude_with_result(
+        &self,
+        id: usize,
+        result: serde_json::Value,
+    ) -> Result<Job, JobRepositoryError>;
     /// Given an input of a job ID, get information about a job tracked by the queue
     async fn find(&self, id: usize) -> Result<Job, JobRepositoryError>;
+    /// Look up several jobs at once, returning a map of the ids that were found.
+    async fn find_many(&self, ids: &[usize]) -> HashMap<usize, Job> {
+        let mut found = HashMap::new();
+        for &id in ids {
+            if let Ok(job) = self.find(id).await {
+                found.insert(id, job);
+            }
+        }
+        found
+    }
     /// Given an input of a job ID, get information about a job tracked by the queue
     async fn cancel(&self, id: usize) -> Result<Job, JobRepositoryError>;
+    /// Record a failed execution. If the job still has attempts left it goes back to
+    /// [`JobStatus::Queued`]; once it exhausts its attempts it lands in the terminal
+    /// [`JobStatus::Dead`] dead-letter state and is no longer dequeued.
+    async fn fail(&self, id: usize) -> Result<Job, JobRepositoryError>;
+    /// List the jobs currently parked in the [`JobStatus::Dead`] dead-letter state.
+    async fn dead(&self) -> Vec<Job>;
+    /// Push the lease deadline of an in-progress job forward so a live worker keeps its claim.
+    ///
+    /// Backends without lease tracking simply echo the current job.
+    async fn heartbeat(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        self.find(id).await
+    }
+    /// Extend the lease of an in-progress job, but only when `runner_id` is its current owner.
+    ///
+    /// Backends without worker-scoped leases ignore the owner and fall back to
+    /// [`JobRepository::heartbeat`].
+    async fn heartbeat_as(
+        &self,
+        id: usize,
+        _runner_id: uuid::Uuid,
+    ) -> Result<Job, JobRepositoryError> {
+        self.heartbeat(id).await
+    }
+    /// Bump the attempt counter of any in-progress job whose lease has expired and return it
+    /// to [`JobStatus::Queued`] in its priority lane, or move it to [`JobStatus::Dead`] once
+    /// it has used up its attempts.
+    ///
+    /// Returns the ids that were reclaimed, including those moved to `Dead`. Backends without lease tracking reclaim nothing.
+    async fn reclaim_expired(&self) -> Vec<usize> {
+        Vec::new()
+    }
+    /// Collect the current job stats as a tuple of
+    /// (`<queued>`, `<in progress>`, `<concluded>`, `<cancelled>`, `<dead>`) counts.
+    async fn stats(&self) -> (usize, usize, usize, usize, usize);
 }
@@ -0,0 +1,364 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::repository::{Job, JobRepository, JobRepositoryError, JobStatus, JobType};
+
+/// Attempts a job gets before it is moved to the dead-letter state.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default visibility timeout applied to a dequeued job before the reaper reclaims it.
+const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(30);
+
+/// A crash-safe [`JobRepository`] backed by an embedded `sled` key-value store.
+///
+/// Every job is persisted under its id in the `jobs` tree as a JSON document, so the
+/// full job map survives a restart. The priority lanes are not stored separately;
+/// instead they are rebuilt on startup by scanning the tree and re-queuing anything
+/// still in [`JobStatus::Queued`], ordered by id so FIFO within each class is preserved.
+#[derive(Clone)]
+pub struct SledJobRepository {
+    db: sled::Db,
+    jobs: sled::Tree,
+    time_critical_queue: Arc<Mutex<VecDeque<usize>>>,
+    not_time_critical_queue: Arc<Mutex<VecDeque<usize>>>,
+    /// Visibility-timeout deadline for each in-progress job. Held in memory (like the lanes);
+    /// any job still `InProgress` at startup is re-leased so a crash can't strand it forever.
+    leases: Arc<Mutex<HashMap<usize, Instant>>>,
+    lease_duration: Duration,
+}
+
+impl SledJobRepository {
+    /// Open (or create) a store rooted at `data_dir` and rebuild the in-memory indices.
+    pub fn open(data_dir: impl AsRef<Path>) -> Result<Self, JobRepositoryError> {
+        let db = sled::open(data_dir).map_err(|_| JobRepositoryError::Unknown)?;
+        let jobs = db.open_tree("jobs").map_err(|_| JobRepositoryError::Unknown)?;
+
+        // Rebuild the in-memory indices from whatever survived the restart: the priority lanes
+        // from still-queued jobs, and a fresh lease for anything left mid-flight so the reaper
+        // can recover it rather than leaving it stranded in `InProgress`.
+        let mut all: Vec<Job> = jobs
+            .iter()
+            .values()
+            .filter_map(|value| value.ok())
+            .filter_map(|bytes| serde_json::from_slice::<Job>(&bytes).ok())
+            .collect();
+        all.sort_by_key(|job| job.id);
+
+        let mut time_critical_queue = VecDeque::new();
+        let mut not_time_critical_queue = VecDeque::new();
+        let mut leases = HashMap::new();
+        let deadline = Instant::now() + DEFAULT_LEASE_DURATION;
+        for job in all {
+            match job.status {
+                JobStatus::Queued => match job.job_type {
+                    JobType::TimeCritical => time_critical_queue.push_back(job.id),
+                    JobType::NotTimeCritical => not_time_critical_queue.push_back(job.id),
+                },
+                JobStatus::InProgress => {
+                    leases.insert(job.id, deadline);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            db,
+            jobs,
+            time_critical_queue: Arc::new(Mutex::new(time_critical_queue)),
+            not_time_critical_queue: Arc::new(Mutex::new(not_time_critical_queue)),
+            leases: Arc::new(Mutex::new(leases)),
+            lease_duration: DEFAULT_LEASE_DURATION,
+        })
+    }
+
+    fn key(id: usize) -> [u8; 8] {
+        (id as u64).to_be_bytes()
+    }
+
+    fn load(&self, id: usize) -> Result<Option<Job>, JobRepositoryError> {
+        self.jobs
+            .get(Self::key(id))
+            .map_err(|_| JobRepositoryError::Unknown)?
+            .map(|bytes| serde_json::from_slice::<Job>(&bytes))
+            .transpose()
+            .map_err(|_| JobRepositoryError::Unknown)
+    }
+
+    /// Mint a fresh, never-reused job id from sled's persisted monotonic counter.
+    ///
+    /// Unlike `jobs.len() + 1`, this never collides under concurrent enqueues and keeps
+    /// advancing across restarts even after jobs are removed from the tree.
+    fn next_id(&self) -> Result<usize, JobRepositoryError> {
+        self.db
+            .generate_id()
+            .map(|id| id as usize + 1)
+            .map_err(|_| JobRepositoryError::Unknown)
+    }
+
+    fn store(&self, job: &Job) -> Result<(), JobRepositoryError> {
+        let bytes = serde_json::to_vec(job).map_err(|_| JobRepositoryError::Unknown)?;
+        self.jobs
+            .insert(Self::key(job.id), bytes)
+            .map_err(|_| JobRepositoryError::Unknown)?;
+        self.db.flush().map_err(|_| JobRepositoryError::Unknown)?;
+        Ok(())
+    }
+
+    /// Append a job id to its priority lane, keeping the critical/standard split.
+    async fn push_lane(&self, job: &Job) {
+        match job.job_type {
+            JobType::TimeCritical => self.time_critical_queue.lock().await.push_back(job.id),
+            JobType::NotTimeCritical => self.not_time_critical_queue.lock().await.push_back(job.id),
+        }
+    }
+
+    /// Remove and return the first id in `lane` whose job is ready to run (its `run_at`, if any,
+    /// has passed), leaving future-dated jobs in place and preserving FIFO among the rest.
+    async fn pop_ready(
+        &self,
+        lane: &Mutex<VecDeque<usize>>,
+    ) -> Result<Option<usize>, JobRepositoryError> {
+        let now = SystemTime::now();
+        let mut queue = lane.lock().await;
+        let mut idx = 0;
+        while idx < queue.len() {
+            match self.load(queue[idx])? {
+                Some(job) if job.run_at.map_or(true, |run_at| run_at <= now) => {
+                    return Ok(queue.remove(idx));
+                }
+                _ => idx += 1,
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl JobRepository for SledJobRepository {
+    async fn enqueue_with_payload(
+        &self,
+        job_type: JobType,
+        payload: Option<serde_json::Value>,
+    ) -> Result<Job, JobRepositoryError> {
+        let id = self.next_id()?;
+        let job = Job {
+            id,
+            job_type,
+            status: JobStatus::Queued,
+            attempts: 0,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            payload,
+            result: None,
+            run_at: None,
+        };
+        self.store(&job)?;
+        self.push_lane(&job).await;
+        Ok(job)
+    }
+
+    async fn enqueue_at(
+        &self,
+        job_type: JobType,
+        run_at: SystemTime,
+    ) -> Result<Job, JobRepositoryError> {
+        let id = self.next_id()?;
+        let job = Job {
+            id,
+            job_type,
+            status: JobStatus::Queued,
+            attempts: 0,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            payload: None,
+            result: None,
+            run_at: Some(run_at),
+        };
+        self.store(&job)?;
+        self.push_lane(&job).await;
+        Ok(job)
+    }
+
+    async fn dequeue(&self) -> Result<Job, JobRepositoryError> {
+        // Serve the time-critical lane first, skipping any scheduled job whose `run_at` is
+        // still in the future so delayed jobs aren't handed out early.
+        let id = match self.pop_ready(&self.time_critical_queue).await? {
+            Some(id) => Some(id),
+            None => self.pop_ready(&self.not_time_critical_queue).await?,
+        };
+        match id {
+            Some(id) => match self.load(id)? {
+                Some(mut job) if job.status == JobStatus::Queued => {
+                    job.status = JobStatus::InProgress;
+                    self.store(&job)?;
+                    // Stamp the visibility timeout so a crashed worker's job can be reclaimed.
+                    self.leases
+                        .lock()
+                        .await
+                        .insert(job.id, Instant::now() + self.lease_duration);
+                    Ok(job)
+                }
+                _ => Err(JobRepositoryError::Unknown),
+            },
+            None => Err(JobRepositoryError::Empty),
+        }
+    }
+
+    async fn conclude(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        match self.load(id)? {
+            Some(mut job) if job.status == JobStatus::InProgress => {
+                job.status = JobStatus::Concluded;
+                self.store(&job)?;
+                self.leases.lock().await.remove(&id);
+                Ok(job)
+            }
+            Some(_) => Err(JobRepositoryError::InvalidStatus(id)),
+            None => Err(JobRepositoryError::NotFound(id)),
+        }
+    }
+
+    async fn conclude_with_result(
+        &self,
+        id: usize,
+        result: serde_json::Value,
+    ) -> Result<Job, JobRepositoryError> {
+        match self.load(id)? {
+            Some(mut job) if job.status == JobStatus::InProgress => {
+                job.status = JobStatus::Concluded;
+                job.result = Some(result);
+                self.store(&job)?;
+                self.leases.lock().await.remove(&id);
+                Ok(job)
+            }
+            Some(_) => Err(JobRepositoryError::InvalidStatus(id)),
+            None => Err(JobRepositoryError::NotFound(id)),
+        }
+    }
+
+    async fn find(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        self.load(id)?.ok_or(JobRepositoryError::NotFound(id))
+    }
+
+    async fn cancel(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        match self.load(id)? {
+            None => Err(JobRepositoryError::NotFound(id)),
+            Some(mut job) => match job.status {
+                JobStatus::Queued | JobStatus::InProgress => {
+                    job.status = JobStatus::Cancelled;
+                    self.store(&job)?;
+                    self.leases.lock().await.remove(&id);
+                    Ok(job)
+                }
+                _ => Err(JobRepositoryError::InvalidStatus(id)),
+            },
+        }
+    }
+
+    async fn fail(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        match self.load(id)? {
+            Some(mut job) if job.status == JobStatus::InProgress => {
+                job.attempts += 1;
+                job.status = if job.attempts < job.max_attempts {
+                    JobStatus::Queued
+                } else {
+                    JobStatus::Dead
+                };
+                self.store(&job)?;
+                self.leases.lock().await.remove(&id);
+                if job.status == JobStatus::Queued {
+                    self.push_lane(&job).await;
+                }
+                Ok(job)
+            }
+            Some(_) => Err(JobRepositoryError::InvalidStatus(id)),
+            None => Err(JobRepositoryError::NotFound(id)),
+        }
+    }
+
+    async fn dead(&self) -> Vec<Job> {
+        let mut dead: Vec<Job> = self
+            .jobs
+            .iter()
+            .values()
+            .filter_map(|value| value.ok())
+            .filter_map(|bytes| serde_json::from_slice::<Job>(&bytes).ok())
+            .filter(|job| job.status == JobStatus::Dead)
+            .collect();
+        dead.sort_by_key(|job| job.id);
+        dead
+    }
+
+    async fn stats(&self) -> (usize, usize, usize, usize, usize) {
+        self.jobs
+            .iter()
+            .values()
+            .filter_map(|value| value.ok())
+            .filter_map(|bytes| serde_json::from_slice::<Job>(&bytes).ok())
+            .fold(
+                (0usize, 0usize, 0usize, 0usize, 0usize),
+                |(queued, in_progress, concluded, cancelled, dead), job| match job.status {
+                    JobStatus::Queued => (queued + 1, in_progress, concluded, cancelled, dead),
+                    JobStatus::InProgress => {
+                        (queued, in_progress + 1, concluded, cancelled, dead)
+                    }
+                    JobStatus::Concluded => (queued, in_progress, concluded + 1, cancelled, dead),
+                    JobStatus::Cancelled => (queued, in_progress, concluded, cancelled + 1, dead),
+                    JobStatus::Dead => (queued, in_progress, concluded, cancelled, dead + 1),
+                },
+            )
+    }
+
+    async fn heartbeat(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        match self.load(id)? {
+            Some(job) if job.status == JobStatus::InProgress => {
+                self.leases
+                    .lock()
+                    .await
+                    .insert(id, Instant::now() + self.lease_duration);
+                Ok(job)
+            }
+            Some(_) => Err(JobRepositoryError::InvalidStatus(id)),
+            None => Err(JobRepositoryError::NotFound(id)),
+        }
+    }
+
+    async fn reclaim_expired(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let expired: Vec<usize> = {
+            let leases = self.leases.lock().await;
+            leases
+                .iter()
+                .filter(|(_, deadline)| **deadline <= now)
+                .map(|(id, _)| *id)
+                .collect()
+        };
+        let mut reclaimed = Vec::new();
+        for id in expired {
+            self.leases.lock().await.remove(&id);
+            let job = match self.load(id) {
+                Ok(Some(mut job)) if job.status == JobStatus::InProgress => {
+                    job.attempts += 1;
+                    job.status = if job.attempts < job.max_attempts {
+                        JobStatus::Queued
+                    } else {
+                        JobStatus::Dead
+                    };
+                    if self.store(&job).is_err() {
+                        continue;
+                    }
+                    job
+                }
+                _ => continue,
+            };
+            if job.status == JobStatus::Queued {
+                self.push_lane(&job).await;
+            }
+            reclaimed.push(id);
+        }
+        reclaimed
+    }
+}
@@ -0,0 +1,270 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::Row;
+
+use crate::repository::{Job, JobRepository, JobRepositoryError, JobStatus, JobType};
+
+/// A persistent [`JobRepository`] backed by SQLite via `sqlx`.
+///
+/// The whole queue lives in a single `jobs` table. `dequeue` claims the next eligible row
+/// with a transactional `UPDATE ... RETURNING`, so two concurrent workers can never be handed
+/// the same job. The numeric `usize` id maps onto an autoincrement integer primary key.
+#[derive(Clone)]
+pub struct SqliteJobRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteJobRepository {
+    /// Connect to `url` (e.g. `sqlite://cfqueue.db?mode=rwc`) and apply the embedded migration.
+    pub async fn connect(url: &str) -> Result<Self, JobRepositoryError> {
+        let pool = SqlitePoolOptions::new()
+            .connect(url)
+            .await
+            .map_err(|_| JobRepositoryError::Unknown)?;
+        let repository = Self { pool };
+        repository.migrate().await?;
+        Ok(repository)
+    }
+
+    async fn migrate(&self) -> Result<(), JobRepositoryError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                type TEXT NOT NULL,
+                status TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                payload TEXT,
+                result TEXT
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|_| JobRepositoryError::Unknown)?;
+        Ok(())
+    }
+}
+
+fn job_type_to_str(job_type: &JobType) -> &'static str {
+    match job_type {
+        JobType::TimeCritical => "TIME_CRITICAL",
+        JobType::NotTimeCritical => "NOT_TIME_CRITICAL",
+    }
+}
+
+fn job_type_from_str(value: &str) -> JobType {
+    match value {
+        "TIME_CRITICAL" => JobType::TimeCritical,
+        _ => JobType::NotTimeCritical,
+    }
+}
+
+fn status_to_str(status: &JobStatus) -> &'static str {
+    match status {
+        JobStatus::Queued => "QUEUED",
+        JobStatus::InProgress => "IN_PROGRESS",
+        JobStatus::Concluded => "CONCLUDED",
+        JobStatus::Cancelled => "CANCELLED",
+        JobStatus::Dead => "DEAD",
+    }
+}
+
+fn status_from_str(value: &str) -> JobStatus {
+    match value {
+        "IN_PROGRESS" => JobStatus::InProgress,
+        "CONCLUDED" => JobStatus::Concluded,
+        "CANCELLED" => JobStatus::Cancelled,
+        "DEAD" => JobStatus::Dead,
+        _ => JobStatus::Queued,
+    }
+}
+
+fn row_to_job(row: &SqliteRow) -> Job {
+    let id: i64 = row.get("id");
+    let job_type: String = row.get("type");
+    let status: String = row.get("status");
+    let attempts: i64 = row.get("attempts");
+    let payload: Option<String> = row.get("payload");
+    let result: Option<String> = row.get("result");
+    Job {
+        id: id as usize,
+        job_type: job_type_from_str(&job_type),
+        status: status_from_str(&status),
+        attempts: attempts as u32,
+        max_attempts: DEFAULT_MAX_ATTEMPTS,
+        payload: payload.and_then(|raw| serde_json::from_str(&raw).ok()),
+        result: result.and_then(|raw| serde_json::from_str(&raw).ok()),
+        run_at: None,
+    }
+}
+
+#[async_trait]
+impl JobRepository for SqliteJobRepository {
+    async fn enqueue_with_payload(
+        &self,
+        job_type: JobType,
+        payload: Option<serde_json::Value>,
+    ) -> Result<Job, JobRepositoryError> {
+        let payload = payload
+            .as_ref()
+            .map(|value| serde_json::to_string(value).unwrap_or_default());
+        let row = sqlx::query(
+            "INSERT INTO jobs (type, status, attempts, payload)
+             VALUES (?1, ?2, 0, ?3) RETURNING *",
+        )
+        .bind(job_type_to_str(&job_type))
+        .bind(status_to_str(&JobStatus::Queued))
+        .bind(payload)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| JobRepositoryError::Unknown)?;
+        Ok(row_to_job(&row))
+    }
+
+    async fn dequeue(&self) -> Result<Job, JobRepositoryError> {
+        // Claim the next queued row atomically, draining time-critical jobs first and
+        // preserving FIFO order within each class via the id tie-breaker.
+        let row = sqlx::query(
+            "UPDATE jobs SET status = ?1
+             WHERE id = (
+                 SELECT id FROM jobs WHERE status = ?2
+                 ORDER BY CASE type WHEN 'TIME_CRITICAL' THEN 0 ELSE 1 END, id
+                 LIMIT 1
+             )
+             RETURNING *",
+        )
+        .bind(status_to_str(&JobStatus::InProgress))
+        .bind(status_to_str(&JobStatus::Queued))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| JobRepositoryError::Unknown)?;
+        row.map(|row| row_to_job(&row))
+            .ok_or(JobRepositoryError::Empty)
+    }
+
+    async fn conclude(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        let row = sqlx::query(
+            "UPDATE jobs SET status = ?1 WHERE id = ?2 AND status = ?3 RETURNING *",
+        )
+        .bind(status_to_str(&JobStatus::Concluded))
+        .bind(id as i64)
+        .bind(status_to_str(&JobStatus::InProgress))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| JobRepositoryError::Unknown)?;
+        match row {
+            Some(row) => Ok(row_to_job(&row)),
+            None => Err(self.missing_or_invalid(id).await),
+        }
+    }
+
+    async fn conclude_with_result(
+        &self,
+        id: usize,
+        result: serde_json::Value,
+    ) -> Result<Job, JobRepositoryError> {
+        let result = serde_json::to_string(&result).map_err(|_| JobRepositoryError::Unknown)?;
+        let row = sqlx::query(
+            "UPDATE jobs SET status = ?1, result = ?2 WHERE id = ?3 AND status = ?4 RETURNING *",
+        )
+        .bind(status_to_str(&JobStatus::Concluded))
+        .bind(result)
+        .bind(id as i64)
+        .bind(status_to_str(&JobStatus::InProgress))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| JobRepositoryError::Unknown)?;
+        match row {
+            Some(row) => Ok(row_to_job(&row)),
+            None => Err(self.missing_or_invalid(id).await),
+        }
+    }
+
+    async fn find(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        let row = sqlx::query("SELECT * FROM jobs WHERE id = ?1")
+            .bind(id as i64)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|_| JobRepositoryError::Unknown)?;
+        row.map(|row| row_to_job(&row))
+            .ok_or(JobRepositoryError::NotFound(id))
+    }
+
+    async fn cancel(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        let row = sqlx::query(
+            "UPDATE jobs SET status = ?1
+             WHERE id = ?2 AND status IN (?3, ?4) RETURNING *",
+        )
+        .bind(status_to_str(&JobStatus::Cancelled))
+        .bind(id as i64)
+        .bind(status_to_str(&JobStatus::Queued))
+        .bind(status_to_str(&JobStatus::InProgress))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| JobRepositoryError::Unknown)?;
+        match row {
+            Some(row) => Ok(row_to_job(&row)),
+            None => Err(self.missing_or_invalid(id).await),
+        }
+    }
+
+    async fn fail(&self, id: usize) -> Result<Job, JobRepositoryError> {
+        let job = self.find(id).await?;
+        if job.status != JobStatus::InProgress {
+            return Err(JobRepositoryError::InvalidStatus(id));
+        }
+        let attempts = job.attempts + 1;
+        let status = if attempts < DEFAULT_MAX_ATTEMPTS {
+            JobStatus::Queued
+        } else {
+            JobStatus::Dead
+        };
+        let row = sqlx::query("UPDATE jobs SET status = ?1, attempts = ?2 WHERE id = ?3 RETURNING *")
+            .bind(status_to_str(&status))
+            .bind(attempts as i64)
+            .bind(id as i64)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|_| JobRepositoryError::Unknown)?;
+        Ok(row_to_job(&row))
+    }
+
+    async fn dead(&self) -> Vec<Job> {
+        sqlx::query("SELECT * FROM jobs WHERE status = ?1 ORDER BY id")
+            .bind(status_to_str(&JobStatus::Dead))
+            .fetch_all(&self.pool)
+            .await
+            .map(|rows| rows.iter().map(row_to_job).collect())
+            .unwrap_or_default()
+    }
+
+    async fn stats(&self) -> (usize, usize, usize, usize, usize) {
+        let count = |status: JobStatus| async move {
+            sqlx::query("SELECT COUNT(*) AS n FROM jobs WHERE status = ?1")
+                .bind(status_to_str(&status))
+                .fetch_one(&self.pool)
+                .await
+                .map(|row| row.get::<i64, _>("n") as usize)
+                .unwrap_or(0)
+        };
+        (
+            count(JobStatus::Queued).await,
+            count(JobStatus::InProgress).await,
+            count(JobStatus::Concluded).await,
+            count(JobStatus::Cancelled).await,
+            count(JobStatus::Dead).await,
+        )
+    }
+}
+
+impl SqliteJobRepository {
+    /// Distinguish a missing row from one in a state that rejected the transition.
+    async fn missing_or_invalid(&self, id: usize) -> JobRepositoryError {
+        match self.find(id).await {
+            Ok(_) => JobRepositoryError::InvalidStatus(id),
+            Err(err) => err,
+        }
+    }
+}
+
+/// Attempts a job gets before it is moved to the dead-letter state.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
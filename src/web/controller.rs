@@ -1,14 +1,81 @@
-use axum::extract::{Path, State};
+use std::collections::HashMap;
+
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::job_repository::{Job, JobRepository, JobType};
+use crate::repository::{Job, JobRepository, JobRepositoryError, JobType};
 use crate::web::{AppError, AppJson};
 use crate::AppState;
 
+/// Visibility timeout applied to a worker-claimed dequeue when the caller doesn't set one.
+const DEFAULT_LEASE_MILLIS: u64 = 30_000;
+
+/// Accepts either a single value or an array of them in the same request body.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(super) enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> From<OneOrMany<T>> for Vec<T> {
+    fn from(value: OneOrMany<T>) -> Self {
+        match value {
+            OneOrMany::One(item) => vec![item],
+            OneOrMany::Many(items) => items,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct FindManyQuery {
+    pub(super) ids: String,
+}
+
+#[derive(Deserialize)]
+pub(super) struct DequeueQuery {
+    /// When set, block for up to this many milliseconds for a job instead of failing fast.
+    pub(super) wait: Option<u64>,
+    /// Worker id claiming the job; it must be presented again to heartbeat or the claim lapses.
+    pub(super) runner: Option<String>,
+    /// Visibility timeout in milliseconds for the claim; defaults to [`DEFAULT_LEASE_MILLIS`].
+    pub(super) lease: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct HeartbeatRequest {
+    /// The worker that claimed the job; only its owner may push the lease forward.
+    #[serde(rename = "RunnerId")]
+    pub(super) runner_id: String,
+}
+
 #[derive(Deserialize)]
 pub(super) struct EnqueueRequest {
     #[serde(rename = "Type")]
     pub(super) job_type: JobType,
+    #[serde(rename = "Payload", default)]
+    pub(super) payload: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ConcludeRequest {
+    #[serde(rename = "Result")]
+    pub(super) result: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ScheduleRequest {
+    #[serde(rename = "Type")]
+    pub(super) job_type: JobType,
+    /// Delay from now, in milliseconds, before the job becomes dequeuable.
+    #[serde(rename = "DelayMillis", default)]
+    pub(super) delay_millis: Option<u64>,
+    /// Absolute time at which the job becomes dequeuable. Takes precedence over `DelayMillis`.
+    #[serde(rename = "RunAt", default)]
+    pub(super) run_at: Option<std::time::SystemTime>,
 }
 
 #[derive(Serialize, Clone)]
@@ -25,6 +92,10 @@ pub(super) struct StatsResponse {
     pub(super) in_progress: usize,
     #[serde(rename = "Concluded")]
     pub(super) concluded: usize,
+    #[serde(rename = "Dead")]
+    pub(super) dead: usize,
+    #[serde(rename = "Running")]
+    pub(super) running: usize,
     #[serde(rename = "UptimeMillis")]
     pub(super) uptime: usize,
 }
@@ -33,12 +104,87 @@ pub(super) async fn enqueue(
     State(state): State<AppState>,
     AppJson(payload): AppJson<EnqueueRequest>,
 ) -> Result<AppJson<EnqueueResponse>, AppError> {
-    let job = state.job_repository.enqueue(payload.job_type).await?;
+    let job = state
+        .job_repository
+        .enqueue_with_payload(payload.job_type, payload.payload)
+        .await?;
     Ok(AppJson(EnqueueResponse { id: job.id }))
 }
 
-pub(super) async fn dequeue(State(state): State<AppState>) -> Result<AppJson<Job>, AppError> {
-    Ok(AppJson(state.job_repository.dequeue().await?))
+pub(super) async fn schedule(
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<ScheduleRequest>,
+) -> Result<AppJson<EnqueueResponse>, AppError> {
+    let run_at = match payload.run_at {
+        Some(run_at) => run_at,
+        None => {
+            std::time::SystemTime::now()
+                + std::time::Duration::from_millis(payload.delay_millis.unwrap_or(0))
+        }
+    };
+    let job = state
+        .job_repository
+        .enqueue_at(payload.job_type, run_at)
+        .await?;
+    Ok(AppJson(EnqueueResponse { id: job.id }))
+}
+
+pub(super) async fn enqueue_batch(
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<OneOrMany<EnqueueRequest>>,
+) -> Result<AppJson<Vec<EnqueueResponse>>, AppError> {
+    let requests: Vec<EnqueueRequest> = payload.into();
+    let jobs = state
+        .job_repository
+        .enqueue_many(
+            requests
+                .into_iter()
+                .map(|request| (request.job_type, request.payload))
+                .collect(),
+        )
+        .await?;
+    Ok(AppJson(
+        jobs.into_iter()
+            .map(|job| EnqueueResponse { id: job.id })
+            .collect(),
+    ))
+}
+
+pub(super) async fn find_many(
+    State(state): State<AppState>,
+    Query(query): Query<FindManyQuery>,
+) -> AppJson<HashMap<usize, Job>> {
+    let ids: Vec<usize> = query
+        .ids
+        .split(',')
+        .filter_map(|id| id.trim().parse::<usize>().ok())
+        .collect();
+    AppJson(state.job_repository.find_many(&ids).await)
+}
+
+pub(super) async fn dequeue(
+    State(state): State<AppState>,
+    Query(query): Query<DequeueQuery>,
+) -> Result<AppJson<Job>, AppError> {
+    let runner = query
+        .runner
+        .as_deref()
+        .and_then(|runner| Uuid::parse_str(runner).ok());
+    let job = match (runner, query.wait) {
+        // A worker claiming the job takes precedence so its lease is owned and reclaimable.
+        (Some(runner), _) => {
+            let lease = Duration::from_millis(query.lease.unwrap_or(DEFAULT_LEASE_MILLIS));
+            state.job_repository.dequeue_as(runner, lease).await?
+        }
+        (None, Some(wait)) => {
+            state
+                .job_repository
+                .dequeue_wait(Duration::from_millis(wait))
+                .await?
+        }
+        (None, None) => state.job_repository.dequeue().await?,
+    };
+    Ok(AppJson(job))
 }
 
 pub(super) async fn conclude(
@@ -48,6 +194,19 @@ pub(super) async fn conclude(
     Ok(AppJson(state.job_repository.conclude(job_id).await?))
 }
 
+pub(super) async fn conclude_with_result(
+    Path(job_id): Path<usize>,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<ConcludeRequest>,
+) -> Result<AppJson<Job>, AppError> {
+    Ok(AppJson(
+        state
+            .job_repository
+            .conclude_with_result(job_id, payload.result)
+            .await?,
+    ))
+}
+
 pub(super) async fn find(
     Path(job_id): Path<usize>,
     State(state): State<AppState>,
@@ -55,15 +214,42 @@ pub(super) async fn find(
     Ok(AppJson(state.job_repository.find(job_id).await?))
 }
 
+pub(super) async fn heartbeat(
+    Path(job_id): Path<usize>,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<HeartbeatRequest>,
+) -> Result<AppJson<Job>, AppError> {
+    let runner_id = Uuid::parse_str(&payload.runner_id)
+        .map_err(|_| JobRepositoryError::InvalidStatus(job_id))?;
+    Ok(AppJson(
+        state.job_repository.heartbeat_as(job_id, runner_id).await?,
+    ))
+}
+
 pub(super) async fn stats(
     State(state): State<AppState>,
 ) -> Result<AppJson<StatsResponse>, AppError> {
-    let (queued, in_progress, concluded) = state.job_repository.stats().await;
+    let (queued, in_progress, concluded, _cancelled, dead) =
+        state.job_repository.stats().await;
     let uptime = state.uptime.elapsed().as_millis() as usize;
+    let running = state.running.load(std::sync::atomic::Ordering::SeqCst);
     Ok(AppJson(StatsResponse {
         queued,
         in_progress,
         concluded,
+        dead,
+        running,
         uptime,
     }))
 }
+
+pub(super) async fn fail(
+    Path(job_id): Path<usize>,
+    State(state): State<AppState>,
+) -> Result<AppJson<Job>, AppError> {
+    Ok(AppJson(state.job_repository.fail(job_id).await?))
+}
+
+pub(super) async fn dead(State(state): State<AppState>) -> AppJson<Vec<Job>> {
+    AppJson(state.job_repository.dead().await)
+}
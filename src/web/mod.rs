@@ -8,7 +8,10 @@ use serde::Serialize;
 use tower_http::trace::TraceLayer;
 
 use crate::repository::JobRepositoryError;
-use crate::web::controller::{conclude, dequeue, enqueue, find, stats};
+use crate::web::controller::{
+    conclude, conclude_with_result, dead, dequeue, enqueue, enqueue_batch, fail, find, find_many,
+    heartbeat, schedule, stats,
+};
 use crate::AppState;
 
 mod controller;
@@ -16,8 +19,16 @@ mod controller;
 pub fn build_server(state: AppState) -> Router {
     Router::new()
         .route("/jobs/enqueue", put(enqueue))
+        .route("/jobs/batch", post(enqueue_batch))
+        .route("/jobs/schedule", put(schedule))
+        .route("/jobs", get(find_many))
         .route("/jobs/dequeue", post(dequeue))
         .route("/jobs/conclude/:job_id", post(conclude))
+        .route("/jobs/:job_id/conclude", put(conclude_with_result))
+        .route("/jobs/:job_id/fail", post(fail))
+        .route("/jobs/fail/:job_id", post(fail))
+        .route("/jobs/:job_id/heartbeat", post(heartbeat))
+        .route("/jobs/dead", get(dead))
         .route("/jobs/:job_id", get(find))
         .route("/jobs/stats", get(stats))
         .layer(